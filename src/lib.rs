@@ -1,11 +1,218 @@
-use std::mem;
-use std::num::{FpCategory, ParseFloatError};
-use std::cmp::Ordering;
-use std::str::FromStr;
-use std::fmt::{Display, LowerExp, UpperExp, Formatter, Error};
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::num::{FpCategory, ParseFloatError};
+use core::cmp::Ordering;
+use core::str::FromStr;
+use core::fmt::{Display, LowerExp, UpperExp, Formatter, Error};
+
+#[cfg(all(feature = "use-intrinsics", any(target_arch = "x86", target_arch = "x86_64")))]
+mod x86;
+
+// Expands a conversion into three variants, selected by cfg: (1) std is available and the target
+// wasn't built with `+f16c`, so check for the feature at runtime and branch; (2) the target was
+// built with `+f16c`, so the intrinsic can be called unconditionally; (3) neither of the above
+// applies (`use-intrinsics` isn't enabled, we're not on x86/x86_64, or std is unavailable and
+// `+f16c` wasn't statically enabled so there's no way to reach the intrinsic), so only the
+// software fallback is ever compiled in.
+macro_rules! convert_fn {
+    (fn $name:ident($arg:ident: $in_ty:ty) -> $ret_ty:ty {
+        if x86_feature("f16c") { $f16c:expr }
+        else { $fallback:expr }
+    }) => {
+        #[cfg(all(feature = "use-intrinsics", feature = "std",
+                  any(target_arch = "x86", target_arch = "x86_64"),
+                  not(target_feature = "f16c")))]
+        #[inline]
+        fn $name($arg: $in_ty) -> $ret_ty {
+            if is_x86_feature_detected!("f16c") {
+                unsafe { $f16c }
+            } else {
+                $fallback
+            }
+        }
+
+        #[cfg(all(feature = "use-intrinsics",
+                  any(target_arch = "x86", target_arch = "x86_64"),
+                  target_feature = "f16c"))]
+        #[inline]
+        fn $name($arg: $in_ty) -> $ret_ty {
+            unsafe { $f16c }
+        }
+
+        #[cfg(not(all(feature = "use-intrinsics",
+                      any(target_arch = "x86", target_arch = "x86_64"),
+                      any(feature = "std", target_feature = "f16c"))))]
+        #[inline]
+        const fn $name($arg: $in_ty) -> $ret_ty {
+            $fallback
+        }
+    };
+}
+
+convert_fn! {
+    fn f16_from_f32(value: f32) -> u16 {
+        if x86_feature("f16c") { x86::f16_from_f32_x86_f16c(value) }
+        else { f16_from_f32_fallback(value) }
+    }
+}
+
+convert_fn! {
+    fn f16_from_f64(value: f64) -> u16 {
+        if x86_feature("f16c") { x86::f16_from_f64_x86_f16c(value) }
+        else { f16_from_f64_fallback(value) }
+    }
+}
+
+convert_fn! {
+    fn f16_to_f32(bits: u16) -> f32 {
+        if x86_feature("f16c") { x86::f16_to_f32_x86_f16c(bits) }
+        else { f16_to_f32_fallback(bits) }
+    }
+}
+
+convert_fn! {
+    fn f16_to_f64(bits: u16) -> f64 {
+        if x86_feature("f16c") { x86::f16_to_f64_x86_f16c(bits) }
+        else { f16_to_f64_fallback(bits) }
+    }
+}
+
+// Slice conversions chunk the buffer into 8- and then 4-wide blocks when F16C (and, for the
+// 8-wide block, AVX) is available, converting each block with a single intrinsic, and fall back
+// to the scalar conversion (which is itself F16C-accelerated per-element where possible) for the
+// remainder. `f16` is `#[repr(transparent)]` over `u16`, so the blocks can read/write through it
+// directly.
+#[cfg(all(feature = "use-intrinsics", any(target_arch = "x86", target_arch = "x86_64")))]
+fn has_f16c() -> bool {
+    if cfg!(target_feature = "f16c") {
+        return true;
+    }
+    #[cfg(feature = "std")]
+    {
+        is_x86_feature_detected!("f16c")
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        false
+    }
+}
+
+#[cfg(all(feature = "use-intrinsics", any(target_arch = "x86", target_arch = "x86_64")))]
+fn has_avx_f16c() -> bool {
+    if cfg!(all(target_feature = "avx", target_feature = "f16c")) {
+        return true;
+    }
+    #[cfg(feature = "std")]
+    {
+        is_x86_feature_detected!("avx") && is_x86_feature_detected!("f16c")
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        false
+    }
+}
+
+// Both arms below are only reachable via f16::from_f32_slice, which allocates a Vec and so is
+// itself gated on `feature = "std"` -- gate these the same way, or they go dead under no_std.
+#[cfg(all(feature = "std", feature = "use-intrinsics", any(target_arch = "x86", target_arch = "x86_64")))]
+fn f16_from_f32_slice(src: &[f32], dst: &mut [f16]) {
+    let mut i = 0;
+    if has_avx_f16c() {
+        while i + 8 <= src.len() {
+            unsafe {
+                x86::f16_from_f32_block8_x86_f16c(src.as_ptr().add(i), dst.as_mut_ptr().add(i) as *mut u16);
+            }
+            i += 8;
+        }
+    }
+    if has_f16c() {
+        while i + 4 <= src.len() {
+            unsafe {
+                x86::f16_from_f32_block4_x86_f16c(src.as_ptr().add(i), dst.as_mut_ptr().add(i) as *mut u16);
+            }
+            i += 4;
+        }
+    }
+    while i < src.len() {
+        dst[i] = f16(f16_from_f32(src[i]));
+        i += 1;
+    }
+}
+
+#[cfg(all(feature = "std", not(all(feature = "use-intrinsics", any(target_arch = "x86", target_arch = "x86_64")))))]
+fn f16_from_f32_slice(src: &[f32], dst: &mut [f16]) {
+    for (s, d) in src.iter().zip(dst.iter_mut()) {
+        *d = f16(f16_from_f32(*s));
+    }
+}
+
+#[cfg(all(feature = "use-intrinsics", any(target_arch = "x86", target_arch = "x86_64")))]
+fn f16_to_f32_slice(src: &[f16], dst: &mut [f32]) {
+    let mut i = 0;
+    if has_avx_f16c() {
+        while i + 8 <= src.len() {
+            unsafe {
+                x86::f16_to_f32_block8_x86_f16c(src.as_ptr().add(i) as *const u16, dst.as_mut_ptr().add(i));
+            }
+            i += 8;
+        }
+    }
+    if has_f16c() {
+        while i + 4 <= src.len() {
+            unsafe {
+                x86::f16_to_f32_block4_x86_f16c(src.as_ptr().add(i) as *const u16, dst.as_mut_ptr().add(i));
+            }
+            i += 4;
+        }
+    }
+    while i < src.len() {
+        dst[i] = f16_to_f32(src[i].0);
+        i += 1;
+    }
+}
+
+#[cfg(not(all(feature = "use-intrinsics", any(target_arch = "x86", target_arch = "x86_64"))))]
+fn f16_to_f32_slice(src: &[f16], dst: &mut [f32]) {
+    for (s, d) in src.iter().zip(dst.iter_mut()) {
+        *d = f16_to_f32(s.0);
+    }
+}
+
+// Binary-search leading-zero-count, so the subnormal-normalization exponent adjustment can be
+// computed in constant time (and in `const fn`s) instead of with a data-dependent loop.
+//
+// When the target statically enables F16C, convert_fn!'s arm 2 calls the intrinsic
+// unconditionally and never falls back to software, so this (and the other fallback helpers
+// below) go unused -- allow that specific, deliberate case rather than the whole crate.
+#[cfg_attr(
+    all(feature = "use-intrinsics", any(target_arch = "x86", target_arch = "x86_64"), target_feature = "f16c"),
+    allow(dead_code)
+)]
+const fn leading_zeros_u16(x: u16) -> u32 {
+    if x == 0 {
+        return 16;
+    }
+    let mut x = x;
+    let mut n = 1u32;
+    if x >> 8 == 0 {
+        n += 8;
+        x <<= 8;
+    }
+    if x >> 12 == 0 {
+        n += 4;
+        x <<= 4;
+    }
+    if x >> 14 == 0 {
+        n += 2;
+        x <<= 2;
+    }
+    n -= (x >> 15) as u32;
+    n
+}
 
 #[allow(non_camel_case_types)]
 #[derive(Debug, Clone, Copy, Default)]
+#[repr(transparent)]
 pub struct f16(u16);
 
 pub const DIGITS: u32 = 3;
@@ -27,255 +234,88 @@ pub const RADIX: u32 = 2;
 
 impl f16 {
     #[inline(always)]
-    pub fn from_bits(bits: u16) -> f16 {
+    pub const fn from_bits(bits: u16) -> f16 {
         f16(bits)
     }
 
+    // The F16C intrinsics can only be selected at runtime (or are simply non-const), so the
+    // conversions are only `const fn` when they're guaranteed to resolve to the pure software
+    // fallback.
+    #[cfg(not(all(feature = "use-intrinsics", any(target_arch = "x86", target_arch = "x86_64"))))]
+    pub const fn from_f32(value: f32) -> f16 {
+        f16(f16_from_f32(value))
+    }
+    #[cfg(all(feature = "use-intrinsics", any(target_arch = "x86", target_arch = "x86_64")))]
     pub fn from_f32(value: f32) -> f16 {
-        // Convert to raw bytes
-        let x: u32 = unsafe { mem::transmute(value) };
-
-        // Check for signed zero
-        if x & 0x7FFFFFFFu32 == 0 {
-            return f16((x >> 16) as u16);
-        }
-
-        // Extract IEEE754 components
-        let sign = x & 0x80000000u32;
-        let exp  = x & 0x7F800000u32;
-        let man  = x & 0x007FFFFFu32;
-
-        // Denormals will underflow, so return signed zero
-        if exp == 0 {
-            return f16((sign >> 16) as u16);
-        }
-
-        // Check for all exponent bits being set, which is Infinity or NaN
-        if exp == 0x7F800000u32 {
-            // A mantissa of zero is a signed Infinity
-            if man == 0 {
-                return f16(((x >> 16) | 0x7C00u32) as u16);
-            }
-            // Otherwise, this is NaN
-            return NAN;
-        }
-
-        // The number is normalized, start assembling half precision version
-        let half_sign = sign >> 16;
-        // Unbias the exponent, then bias for half precision
-        let unbiased_exp = (exp >> 23) - 127;
-        let half_exp = unbiased_exp + 15;
-
-        // Check for exponent overflow, return +infinity
-        if half_exp >= 0x1F {
-            return f16((half_sign | 0x7C00u32) as u16);
-        }
-
-        // Check for underflow
-        if half_exp <= 0 {
-            // Check mantissa for what we can do
-            if 14 - half_exp > 24 {
-                // No rounding possibility, so this is a full underflow, return signed zero
-                return f16(half_sign as u16);
-            }
-            // Don't forget about hidden leading mantissa bit when assembling mantissa
-            let man = man | 0x00800000u32;
-            let mut half_man = man >> (14 - half_exp);
-            // Check for rounding
-            if (man >> (13 - half_exp)) & 0x1u32 != 0 {
-                half_man += 1;
-            }
-            // No exponent for denormals
-            return f16((half_sign | half_man) as u16);
-        }
-
-        // Rebias the exponent
-        let half_exp = half_exp << 10;
-        let half_man = man >> 13;
-        // Check for rounding
-        if man & 0x00001000u32 != 0 {
-            // Round it
-            f16(((half_sign | half_exp | half_man) + 1) as u16)
-        } else {
-            f16((half_sign | half_exp | half_man) as u16)
-        }
+        f16(f16_from_f32(value))
     }
 
+    #[cfg(not(all(feature = "use-intrinsics", any(target_arch = "x86", target_arch = "x86_64"))))]
+    pub const fn from_f64(value: f64) -> f16 {
+        f16(f16_from_f64(value))
+    }
+    #[cfg(all(feature = "use-intrinsics", any(target_arch = "x86", target_arch = "x86_64")))]
     pub fn from_f64(value: f64) -> f16 {
-        // Convert to raw bytes, truncating the last 32-bits of mantissa; that precision will always
-        // be lost on half-precision.
-        let val: u64 = unsafe { mem::transmute(value) };
-        let x = (val >> 32) as u32;
-
-        // Check for signed zero
-        if x & 0x7FFFFFFFu32 == 0 {
-            return f16((x >> 16) as u16);
-        }
-
-        // Extract IEEE754 components
-        let sign = x & 0x80000000u32;
-        let exp  = x & 0x7FF00000u32;
-        let man  = x & 0x000FFFFFu32;
-
-        // Denormals will underflow, so return signed zero
-        if exp == 0 {
-            return f16((sign >> 16) as u16);
-        }
-
-        // Check for all exponent bits being set, which is Infinity or NaN
-        if exp == 0x7FF00000u32 {
-            // A mantissa of zero is a signed Infinity
-            if man == 0 {
-                return f16(((x >> 16) | 0x7C00u32) as u16);
-            }
-            // Otherwise, this is NaN
-            return NAN;
-        }
-
-        // The number is normalized, start assembling half precision version
-        let half_sign = sign >> 16;
-        // Unbias the exponent, then bias for half precision
-        let unbiased_exp = (exp >> 20) - 1023;
-        let half_exp = unbiased_exp + 15;
-
-        // Check for exponent overflow, return +infinity
-        if half_exp >= 0x1F {
-            return f16((half_sign | 0x7C00u32) as u16);
-        }
-
-        // Check for underflow
-        if half_exp <= 0 {
-            // Check mantissa for what we can do
-            if 10 - half_exp > 21 {
-                // No rounding possibility, so this is a full underflow, return signed zero
-                return f16(half_sign as u16);
-            }
-            // Don't forget about hidden leading mantissa bit when assembling mantissa
-            let man = man | 0x00100000u32;
-            let mut half_man = man >> (11 - half_exp);
-            // Check for rounding
-            if (man >> (10 - half_exp)) & 0x1u32 != 0 {
-                half_man += 1;
-            }
-            // No exponent for denormals
-            return f16((half_sign | half_man) as u16);
-        }
-
-        // Rebias the exponent
-        let half_exp = half_exp << 10;
-        let half_man = man >> 10;
-        // Check for rounding
-        if man & 0x00000200u32 != 0 {
-            // Round it
-            f16(((half_sign | half_exp | half_man) + 1) as u16)
-        } else {
-            f16((half_sign | half_exp | half_man) as u16)
-        }
+        f16(f16_from_f64(value))
     }
 
     #[inline(always)]
-    pub fn as_bits(self) -> u16 {
+    pub const fn as_bits(self) -> u16 {
         self.0
     }
 
+    #[cfg(not(all(feature = "use-intrinsics", any(target_arch = "x86", target_arch = "x86_64"))))]
+    const fn to_f32(self) -> f32 {
+        f16_to_f32(self.0)
+    }
+    #[cfg(all(feature = "use-intrinsics", any(target_arch = "x86", target_arch = "x86_64")))]
     fn to_f32(self) -> f32 {
-        // Check for signed zero
-        if self.0 & 0x7FFFu16 == 0 {
-            return unsafe { mem::transmute((self.0 as u32) << 16) };
-        }
-
-        let half_sign = (self.0 & 0x8000u16) as u32;
-        let half_exp  = (self.0 & 0x7C00u16) as u32;
-        let half_man  = (self.0 & 0x03FFu16) as u32;
-
-        // Check for an infinity or NaN when all exponent bits set
-        if half_exp == 0x7C00u32 {
-            // Check for signed infinity if mantissa is zero
-            if half_man == 0 {
-                return unsafe { mem::transmute((half_sign << 16) | 0x7F800000u32) };
-            } else {
-                // NaN, only 1st mantissa bit is set
-                return unsafe { mem::transmute(0xFFC00000u32) };
-            }
-        }
-
-        // Calculate single-precision components with adjusted exponent
-        let sign = half_sign << 16;
-        // Unbias exponent
-        let unbiased_exp = ((half_exp as i32) >> 10) - 15;
-        let man = (half_man & 0x03FFu32) << 13;
-
-        // Check for denormals, which will be normalized by adjusting exponent
-        if half_exp == 0 {
-            // Calculate how much to adjust the exponent by
-            let e = {
-                let mut e_adj = 0;
-                let mut hm_adj = half_man << 1;
-                while hm_adj & 0x0400u32 == 0 {
-                    e_adj += 1;
-                    hm_adj <<= 1;
-                }
-                e_adj
-            };
-
-            // Rebias and adjust exponent
-            let exp = ((unbiased_exp + 127 - e) << 23) as u32;
-            return unsafe { mem::transmute(sign | exp | man) };
-        }
-
-        // Rebias exponent for a normalized normal
-        let exp = ((unbiased_exp + 127) << 23) as u32;
-        unsafe { mem::transmute(sign | exp | man) }
+        f16_to_f32(self.0)
     }
 
+    #[cfg(not(all(feature = "use-intrinsics", any(target_arch = "x86", target_arch = "x86_64"))))]
+    const fn to_f64(self) -> f64 {
+        f16_to_f64(self.0)
+    }
+    #[cfg(all(feature = "use-intrinsics", any(target_arch = "x86", target_arch = "x86_64")))]
     fn to_f64(self) -> f64 {
-        // Check for signed zero
-        if self.0 & 0x7FFFu16 == 0 {
-            return unsafe { mem::transmute((self.0 as u64) << 48) };
-        }
+        f16_to_f64(self.0)
+    }
 
-        let half_sign = (self.0 & 0x8000u16) as u64;
-        let half_exp  = (self.0 & 0x7C00u16) as u64;
-        let half_man  = (self.0 & 0x03FFu16) as u64;
+    /// Converts a slice of `f32` values into a newly allocated `Vec` of `f16`.
+    #[cfg(feature = "std")]
+    pub fn from_f32_slice(slice: &[f32]) -> Vec<f16> {
+        let mut dst = vec![f16(0); slice.len()];
+        f16_from_f32_slice(slice, &mut dst);
+        dst
+    }
 
-        // Check for an infinity or NaN when all exponent bits set
-        if half_exp == 0x7C00u64 {
-            // Check for signed infinity if mantissa is zero
-            if half_man == 0 {
-                return unsafe { mem::transmute((half_sign << 48) | 0x7FF0000000000000u64) };
-            } else {
-                // NaN, only 1st mantissa bit is set
-                return unsafe { mem::transmute(0xFFF8000000000000u64) };
-            }
-        }
+    /// Converts a slice of `f64` values into a newly allocated `Vec` of `f16`.
+    #[cfg(feature = "std")]
+    pub fn from_f64_slice(slice: &[f64]) -> Vec<f16> {
+        slice.iter().map(|&value| f16::from_f64(value)).collect()
+    }
 
-        // Calculate double-precision components with adjusted exponent
-        let sign = half_sign << 48;
-        // Unbias exponent
-        let unbiased_exp = ((half_exp as i64) >> 10) - 15;
-        let man = (half_man & 0x03FFu64) << 42;
-
-        // Check for denormals, which will be normalized by adjusting exponent
-        if half_exp == 0 {
-            // Calculate how much to adjust the exponent by
-            let e = {
-                let mut e_adj = 0;
-                let mut hm_adj = half_man << 1;
-                while hm_adj & 0x0400u64 == 0 {
-                    e_adj += 1;
-                    hm_adj <<= 1;
-                }
-                e_adj
-            };
-
-            // Rebias and adjust exponent
-            let exp = ((unbiased_exp + 1023 - e) << 52) as u64;
-            return unsafe { mem::transmute(sign | exp | man) };
-        }
+    /// Converts a slice of `f16` values into `dst`, element-wise, without allocating.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slice` and `dst` don't have the same length.
+    pub fn to_f32_slice(slice: &[f16], dst: &mut [f32]) {
+        assert_eq!(slice.len(), dst.len());
+        f16_to_f32_slice(slice, dst);
+    }
 
-        // Rebias exponent for a normalized normal
-        let exp = ((unbiased_exp + 1023) << 52) as u64;
-        unsafe { mem::transmute(sign | exp | man) }
+    /// Converts a slice of `f16` values into `dst`, element-wise, without allocating.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slice` and `dst` don't have the same length.
+    pub fn to_f64_slice(slice: &[f16], dst: &mut [f64]) {
+        assert_eq!(slice.len(), dst.len());
+        for (s, d) in slice.iter().zip(dst.iter_mut()) {
+            *d = f16_to_f64(s.0);
+        }
     }
 
     #[inline]
@@ -338,6 +378,266 @@ impl f16 {
     }
 }
 
+// Unused when the target statically enables F16C -- see the comment on leading_zeros_u16.
+#[cfg_attr(
+    all(feature = "use-intrinsics", any(target_arch = "x86", target_arch = "x86_64"), target_feature = "f16c"),
+    allow(dead_code)
+)]
+const fn f16_from_f32_fallback(value: f32) -> u16 {
+    // Convert to raw bytes
+    let x: u32 = value.to_bits();
+
+    // Check for signed zero
+    if x & 0x7FFFFFFFu32 == 0 {
+        return (x >> 16) as u16;
+    }
+
+    // Extract IEEE754 components
+    let sign = x & 0x80000000u32;
+    let exp  = x & 0x7F800000u32;
+    let man  = x & 0x007FFFFFu32;
+
+    // Denormals will underflow, so return signed zero
+    if exp == 0 {
+        return (sign >> 16) as u16;
+    }
+
+    // Check for all exponent bits being set, which is Infinity or NaN
+    if exp == 0x7F800000u32 {
+        // A mantissa of zero is a signed Infinity
+        if man == 0 {
+            return ((x >> 16) | 0x7C00u32) as u16;
+        }
+        // Otherwise, this is NaN
+        return NAN.0;
+    }
+
+    // The number is normalized, start assembling half precision version
+    let half_sign = sign >> 16;
+    // Unbias the exponent, then bias for half precision. Signed so that values with magnitude
+    // below 1.0 (negative unbiased_exp) don't underflow the subtraction.
+    let unbiased_exp = (exp >> 23) as i32 - 127;
+    let half_exp = unbiased_exp + 15;
+
+    // Check for exponent overflow, return +infinity
+    if half_exp >= 0x1F {
+        return (half_sign | 0x7C00u32) as u16;
+    }
+
+    // Check for underflow
+    if half_exp <= 0 {
+        // Check mantissa for what we can do
+        if 14 - half_exp > 24 {
+            // No rounding possibility, so this is a full underflow, return signed zero
+            return half_sign as u16;
+        }
+        // Don't forget about hidden leading mantissa bit when assembling mantissa
+        let man = man | 0x00800000u32;
+        let mut half_man = man >> (14 - half_exp) as u32;
+        // Round to nearest even: round up only if the rounding bit is set and either a sticky
+        // bit below it is set or the retained mantissa's LSB is set (breaking exact ties to even).
+        let round_bit = 1u32 << (13 - half_exp) as u32;
+        if (man & round_bit) != 0 && (man & (3 * round_bit - 1)) != 0 {
+            half_man += 1;
+        }
+        // No exponent for denormals
+        return (half_sign | half_man) as u16;
+    }
+
+    // Rebias the exponent
+    let half_exp = (half_exp as u32) << 10;
+    let half_man = man >> 13;
+    // Round to nearest even: round up only if the rounding bit is set and either a sticky bit
+    // below it is set or the retained mantissa's LSB is set (breaking exact ties to even).
+    let round_bit = 0x0000_1000u32;
+    if (man & round_bit) != 0 && (man & (3 * round_bit - 1)) != 0 {
+        // Round it
+        ((half_sign | half_exp | half_man) + 1) as u16
+    } else {
+        (half_sign | half_exp | half_man) as u16
+    }
+}
+
+// Unused when the target statically enables F16C -- see the comment on leading_zeros_u16.
+#[cfg_attr(
+    all(feature = "use-intrinsics", any(target_arch = "x86", target_arch = "x86_64"), target_feature = "f16c"),
+    allow(dead_code)
+)]
+const fn f16_from_f64_fallback(value: f64) -> u16 {
+    // Convert to raw bytes, truncating the last 32-bits of mantissa; that precision will always
+    // be lost on half-precision.
+    let val: u64 = value.to_bits();
+    let x = (val >> 32) as u32;
+
+    // Check for signed zero
+    if x & 0x7FFFFFFFu32 == 0 {
+        return (x >> 16) as u16;
+    }
+
+    // Extract IEEE754 components
+    let sign = x & 0x80000000u32;
+    let exp  = x & 0x7FF00000u32;
+    let man  = x & 0x000FFFFFu32;
+
+    // Denormals will underflow, so return signed zero
+    if exp == 0 {
+        return (sign >> 16) as u16;
+    }
+
+    // Check for all exponent bits being set, which is Infinity or NaN
+    if exp == 0x7FF00000u32 {
+        // A mantissa of zero is a signed Infinity
+        if man == 0 {
+            return ((x >> 16) | 0x7C00u32) as u16;
+        }
+        // Otherwise, this is NaN
+        return NAN.0;
+    }
+
+    // The number is normalized, start assembling half precision version
+    let half_sign = sign >> 16;
+    // Unbias the exponent, then bias for half precision. Signed so that values with magnitude
+    // below 1.0 (negative unbiased_exp) don't underflow the subtraction.
+    let unbiased_exp = (exp >> 20) as i64 - 1023;
+    let half_exp = unbiased_exp + 15;
+
+    // Check for exponent overflow, return +infinity
+    if half_exp >= 0x1F {
+        return (half_sign | 0x7C00u32) as u16;
+    }
+
+    // Check for underflow
+    if half_exp <= 0 {
+        // Check mantissa for what we can do
+        if 10 - half_exp > 21 {
+            // No rounding possibility, so this is a full underflow, return signed zero
+            return half_sign as u16;
+        }
+        // Don't forget about hidden leading mantissa bit when assembling mantissa
+        let man = man | 0x00100000u32;
+        let mut half_man = man >> (11 - half_exp) as u32;
+        // Round to nearest even: round up only if the rounding bit is set and either a sticky
+        // bit below it is set or the retained mantissa's LSB is set (breaking exact ties to even).
+        let round_bit = 1u32 << (10 - half_exp) as u32;
+        if (man & round_bit) != 0 && (man & (3 * round_bit - 1)) != 0 {
+            half_man += 1;
+        }
+        // No exponent for denormals
+        return (half_sign | half_man) as u16;
+    }
+
+    // Rebias the exponent
+    let half_exp = (half_exp as u32) << 10;
+    let half_man = man >> 10;
+    // Round to nearest even: round up only if the rounding bit is set and either a sticky bit
+    // below it is set or the retained mantissa's LSB is set (breaking exact ties to even).
+    let round_bit = 0x0000_0200u32;
+    if (man & round_bit) != 0 && (man & (3 * round_bit - 1)) != 0 {
+        // Round it
+        ((half_sign | half_exp | half_man) + 1) as u16
+    } else {
+        (half_sign | half_exp | half_man) as u16
+    }
+}
+
+// Unused when the target statically enables F16C -- see the comment on leading_zeros_u16.
+#[cfg_attr(
+    all(feature = "use-intrinsics", any(target_arch = "x86", target_arch = "x86_64"), target_feature = "f16c"),
+    allow(dead_code)
+)]
+const fn f16_to_f32_fallback(bits: u16) -> f32 {
+    // Check for signed zero
+    if bits & 0x7FFFu16 == 0 {
+        return f32::from_bits((bits as u32) << 16);
+    }
+
+    let half_sign = (bits & 0x8000u16) as u32;
+    let half_exp  = (bits & 0x7C00u16) as u32;
+    let half_man  = (bits & 0x03FFu16) as u32;
+
+    // Check for an infinity or NaN when all exponent bits set
+    if half_exp == 0x7C00u32 {
+        // Check for signed infinity if mantissa is zero
+        if half_man == 0 {
+            return f32::from_bits((half_sign << 16) | 0x7F800000u32);
+        } else {
+            // NaN, only 1st mantissa bit is set
+            return f32::from_bits(0xFFC00000u32);
+        }
+    }
+
+    // Calculate single-precision components with adjusted exponent
+    let sign = half_sign << 16;
+    // Unbias exponent
+    let unbiased_exp = ((half_exp as i32) >> 10) - 15;
+    let man = (half_man & 0x03FFu32) << 13;
+
+    // Check for denormals, which will be normalized by adjusting exponent
+    if half_exp == 0 {
+        // Calculate how much to adjust the exponent by
+        let e = leading_zeros_u16(half_man as u16) - 6;
+
+        // Rebias and adjust exponent
+        let exp = ((unbiased_exp + 127 - e as i32) << 23) as u32;
+        // Shift the leading 1 out into the implied bit position
+        let man = ((half_man << (1 + e)) & 0x03FFu32) << 13;
+        return f32::from_bits(sign | exp | man);
+    }
+
+    // Rebias exponent for a normalized normal
+    let exp = ((unbiased_exp + 127) << 23) as u32;
+    f32::from_bits(sign | exp | man)
+}
+
+// Unused when the target statically enables F16C -- see the comment on leading_zeros_u16.
+#[cfg_attr(
+    all(feature = "use-intrinsics", any(target_arch = "x86", target_arch = "x86_64"), target_feature = "f16c"),
+    allow(dead_code)
+)]
+const fn f16_to_f64_fallback(bits: u16) -> f64 {
+    // Check for signed zero
+    if bits & 0x7FFFu16 == 0 {
+        return f64::from_bits((bits as u64) << 48);
+    }
+
+    let half_sign = (bits & 0x8000u16) as u64;
+    let half_exp  = (bits & 0x7C00u16) as u64;
+    let half_man  = (bits & 0x03FFu16) as u64;
+
+    // Check for an infinity or NaN when all exponent bits set
+    if half_exp == 0x7C00u64 {
+        // Check for signed infinity if mantissa is zero
+        if half_man == 0 {
+            return f64::from_bits((half_sign << 48) | 0x7FF0000000000000u64);
+        } else {
+            // NaN, only 1st mantissa bit is set
+            return f64::from_bits(0xFFF8000000000000u64);
+        }
+    }
+
+    // Calculate double-precision components with adjusted exponent
+    let sign = half_sign << 48;
+    // Unbias exponent
+    let unbiased_exp = ((half_exp as i64) >> 10) - 15;
+    let man = (half_man & 0x03FFu64) << 42;
+
+    // Check for denormals, which will be normalized by adjusting exponent
+    if half_exp == 0 {
+        // Calculate how much to adjust the exponent by
+        let e = leading_zeros_u16(half_man as u16) - 6;
+
+        // Rebias and adjust exponent
+        let exp = ((unbiased_exp + 1023 - e as i64) << 52) as u64;
+        // Shift the leading 1 out into the implied bit position
+        let man = ((half_man << (1 + e)) & 0x03FFu64) << 42;
+        return f64::from_bits(sign | exp | man);
+    }
+
+    // Rebias exponent for a normalized normal
+    let exp = ((unbiased_exp + 1023) << 52) as u64;
+    f64::from_bits(sign | exp | man)
+}
+
 impl From<f16> for f32 {
     fn from(x: f16) -> f32 {
         x.to_f32()
@@ -403,7 +703,7 @@ impl PartialOrd for f16 {
 impl FromStr for f16 {
     type Err = ParseFloatError;
     fn from_str(src: &str) -> Result<f16, ParseFloatError> {
-        f32::from_str(src).map(|x| f16::from_f32(x))
+        f32::from_str(src).map(f16::from_f32)
     }
 }
 
@@ -423,4 +723,285 @@ impl UpperExp for f16 {
     fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
         write!(f, "{:E}", self.to_f32())
     }
-}
\ No newline at end of file
+}
+
+/// A 16-bit "brain" floating point type.
+///
+/// Unlike `f16`, `bf16` keeps the full 8-bit exponent of `f32` and only truncates the mantissa
+/// down to 7 bits. This trades precision for range, matching `f32`'s range exactly, which is
+/// the tradeoff most machine learning workloads want.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct bf16(u16);
+
+impl bf16 {
+    pub const DIGITS: u32 = 2;
+    pub const EPSILON: bf16 = bf16(0x3C00u16); // 0.0078125
+    pub const INFINITY: bf16 = bf16(0x7F80u16);
+    pub const MANTISSA_DIGITS: u32 = 8;
+    pub const MAX: bf16 = bf16(0x7F7Fu16);
+    pub const MAX_10_EXP: i32 = 38;
+    pub const MAX_EXP: i32 = 128;
+    pub const MIN: bf16 = bf16(0xFF7Fu16);
+    pub const MIN_10_EXP: i32 = -37;
+    pub const MIN_EXP: i32 = -125;
+    pub const MIN_POSITIVE: bf16 = bf16(0x0080u16);
+    pub const NAN: bf16 = bf16(0xFFC0u16);
+    pub const NEG_INFINITY: bf16 = bf16(0xFF80u16);
+    pub const RADIX: u32 = 2;
+
+    #[inline(always)]
+    pub const fn from_bits(bits: u16) -> bf16 {
+        bf16(bits)
+    }
+
+    pub const fn from_f32(value: f32) -> bf16 {
+        bf16(f32_to_bf16_bits(value.to_bits()))
+    }
+
+    pub const fn from_f64(value: f64) -> bf16 {
+        // Narrow to f32 first, then reuse the f32 path.
+        bf16(f32_to_bf16_bits((value as f32).to_bits()))
+    }
+
+    #[inline(always)]
+    pub const fn as_bits(self) -> u16 {
+        self.0
+    }
+
+    const fn to_f32(self) -> f32 {
+        f32::from_bits((self.0 as u32) << 16)
+    }
+
+    const fn to_f64(self) -> f64 {
+        self.to_f32() as f64
+    }
+
+    #[inline]
+    pub fn is_nan(self) -> bool {
+        (self.0 & 0x7F80u16 == 0x7F80u16) && (self.0 & 0x007Fu16 != 0)
+    }
+
+    #[inline]
+    pub fn is_infinite(self) -> bool {
+        (self.0 & 0x7F80u16 == 0x7F80u16) && (self.0 & 0x007Fu16 == 0)
+    }
+
+    #[inline]
+    pub fn is_finite(self) -> bool {
+        self.0 & 0x7F80u16 != 0x7F80u16
+    }
+
+    #[inline]
+    pub fn is_normal(self) -> bool {
+        let exp = self.0 & 0x7F80u16;
+        exp != 0x7F80u16 && exp != 0
+    }
+
+    pub fn classify(self) -> FpCategory {
+        let exp = self.0 & 0x7F80u16;
+        let man = self.0 & 0x007Fu16;
+        if exp == 0 {
+            if man == 0 {
+                FpCategory::Zero
+            } else {
+                FpCategory::Subnormal
+            }
+        } else if exp == 0x7F80u16 {
+            if man == 0 {
+                FpCategory::Infinite
+            } else {
+                FpCategory::Nan
+            }
+        } else {
+            FpCategory::Normal
+        }
+    }
+
+    pub fn signum(self) -> bf16 {
+        if self.is_nan() {
+            self
+        } else {
+            bf16(self.0 & 0x8000u16)
+        }
+    }
+
+    #[inline]
+    pub fn is_sign_positive(self) -> bool {
+        self.0 & 0x8000u16 == 0
+    }
+
+    #[inline]
+    pub fn is_sign_negative(self) -> bool {
+        self.0 & 0x8000u16 != 0
+    }
+}
+
+// f32 -> bf16 is simply the high 16 bits of the f32 bit pattern, round-to-nearest-even.
+const fn f32_to_bf16_bits(x: u32) -> u16 {
+    // Check for NaN, preserving a mantissa bit so it stays NaN.
+    if x & 0x7FFF_FFFFu32 > 0x7F80_0000u32 {
+        return ((x >> 16) | 0x0040u32) as u16;
+    }
+
+    // Round to nearest even.
+    let round_bit = 0x0000_8000u32;
+    if (x & round_bit) != 0 && (x & (3 * round_bit - 1)) != 0 {
+        ((x >> 16) + 1) as u16
+    } else {
+        (x >> 16) as u16
+    }
+}
+
+impl From<bf16> for f32 {
+    fn from(x: bf16) -> f32 {
+        x.to_f32()
+    }
+}
+
+impl From<bf16> for f64 {
+    fn from(x: bf16) -> f64 {
+        x.to_f64()
+    }
+}
+
+impl From<i8> for bf16 {
+    fn from(x: i8) -> bf16 {
+        // Convert to f32, then to bf16
+        bf16::from_f32(f32::from(x))
+    }
+}
+
+impl From<u8> for bf16 {
+    fn from(x: u8) -> bf16 {
+        // Convert to f32, then to bf16
+        bf16::from_f32(f32::from(x))
+    }
+}
+
+impl PartialEq for bf16 {
+    fn eq(&self, other: &bf16) -> bool {
+        !self.is_nan() && !other.is_nan() && self.0 == other.0
+    }
+}
+
+impl PartialOrd for bf16 {
+    fn partial_cmp(&self, other: &bf16) -> Option<Ordering> {
+        if self.is_nan() || other.is_nan() {
+            None
+        } else if self.0 == other.0 {
+            Some(Ordering::Equal)
+        } else if self.0 < other.0 {
+            Some(Ordering::Less)
+        } else {
+            Some(Ordering::Greater)
+        }
+    }
+
+    fn lt(&self, other: &bf16) -> bool {
+        !self.is_nan() && !other.is_nan() && self.0 < other.0
+    }
+
+    fn le(&self, other: &bf16) -> bool {
+        !self.is_nan() && !other.is_nan() && self.0 <= other.0
+    }
+
+    fn gt(&self, other: &bf16) -> bool {
+        !self.is_nan() && !other.is_nan() && self.0 > other.0
+    }
+
+    fn ge(&self, other: &bf16) -> bool {
+        !self.is_nan() && !other.is_nan() && self.0 >= other.0
+    }
+}
+
+impl FromStr for bf16 {
+    type Err = ParseFloatError;
+    fn from_str(src: &str) -> Result<bf16, ParseFloatError> {
+        f32::from_str(src).map(bf16::from_f32)
+    }
+}
+
+impl Display for bf16 {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write!(f, "{}", self.to_f32())
+    }
+}
+
+impl LowerExp for bf16 {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write!(f, "{:e}", self.to_f32())
+    }
+}
+
+impl UpperExp for bf16 {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write!(f, "{:E}", self.to_f32())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn f16_from_f32_small_magnitudes() {
+        // Values with magnitude < 1.0 exercise the denormal/underflow branch of the software
+        // fallback; these previously underflowed the (unsigned) exponent subtraction and came
+        // out as +/-infinity instead of a denormal or zero.
+        assert_eq!(f16::from_f32(0.5).as_bits(), 0x3800);
+        assert_eq!(f16::from_f32(1e-5).as_bits(), 0x00a8);
+        assert_eq!(f16::from_f32(1e-6).as_bits(), 0x0011);
+        assert_eq!(f16::from_f32(1e-7).as_bits(), 0x0002);
+        assert_eq!(f16::from_f32(1e-30).as_bits(), 0x0000);
+    }
+
+    #[test]
+    fn f16_round_trip() {
+        for &value in &[0.0f32, -0.0, 1.0, -1.0, 0.5, 100.0, -123.25, 65504.0] {
+            let roundtripped = f16::from_f32(value).to_f32();
+            assert_eq!(roundtripped, value, "value = {}", value);
+        }
+    }
+
+    #[test]
+    fn f16_from_f64_matches_f32_path() {
+        for &value in &[0.5f64, 1e-5, 1e10, -123.25] {
+            assert_eq!(f16::from_f64(value), f16::from_f32(value as f32));
+        }
+    }
+
+    #[test]
+    fn bf16_from_f64_narrows_through_f32() {
+        // bf16::from_f64 previously reused the high 32 bits of the f64 bit pattern directly,
+        // misreading f64's layout as f32's.
+        assert_eq!(bf16::from_f64(1.0).to_f32(), 1.0);
+        assert_eq!(bf16::from_f64(100.0).to_f32(), 100.0);
+        assert_eq!(bf16::from_f64(1.0), bf16::from_f32(1.0));
+    }
+
+    #[test]
+    fn f16_round_to_nearest_even() {
+        // f16 spacing at 2048 is 2. 2048.5 and 2049.5 are not ties and round to the nearest value.
+        assert_eq!(f16::from_f32(2048.5).to_f32(), 2048.0);
+        assert_eq!(f16::from_f32(2049.5).to_f32(), 2050.0);
+        // 2049.0 and 2051.0 are exact ties and round to the even mantissa (2048 and 2052).
+        assert_eq!(f16::from_f32(2049.0).to_f32(), 2048.0);
+        assert_eq!(f16::from_f32(2051.0).to_f32(), 2052.0);
+    }
+
+    #[test]
+    fn f16_slice_conversions_match_scalar_path() {
+        // With `use-intrinsics` on an F16C/AVX machine, 13 elements exercises the 8-wide block,
+        // then the 4-wide block, then a 1-element scalar remainder, so an off-by-one in any
+        // block's chunk/remainder bookkeeping would show up here.
+        let src: Vec<f32> = (0..13).map(|i| i as f32 * 0.5 - 3.0).collect();
+        let expected: Vec<f16> = src.iter().map(|&v| f16::from_f32(v)).collect();
+
+        let converted = f16::from_f32_slice(&src);
+        assert_eq!(converted, expected);
+
+        let mut back = vec![0.0f32; src.len()];
+        f16::to_f32_slice(&converted, &mut back);
+        assert_eq!(back, src);
+    }
+}
@@ -0,0 +1,82 @@
+//! F16C intrinsic wrappers for hardware-accelerated `f16` conversions.
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+// convert_fn! only calls these when std is available (runtime feature check) or the target
+// statically enables f16c; with neither, it compiles the software fallback instead and these go
+// unused -- allow that specific, deliberate case rather than the whole crate.
+#[cfg_attr(not(any(feature = "std", target_feature = "f16c")), allow(dead_code))]
+#[target_feature(enable = "f16c")]
+#[inline]
+pub unsafe fn f16_from_f32_x86_f16c(value: f32) -> u16 {
+    let value = _mm_set_ss(value);
+    let result = _mm_cvtps_ph(value, _MM_FROUND_TO_NEAREST_INT);
+    _mm_cvtsi128_si32(result) as u16
+}
+
+#[cfg_attr(not(any(feature = "std", target_feature = "f16c")), allow(dead_code))]
+#[target_feature(enable = "f16c")]
+#[inline]
+pub unsafe fn f16_from_f64_x86_f16c(value: f64) -> u16 {
+    // Narrow to f32 first, then use the f32 intrinsic.
+    f16_from_f32_x86_f16c(value as f32)
+}
+
+#[cfg_attr(not(any(feature = "std", target_feature = "f16c")), allow(dead_code))]
+#[target_feature(enable = "f16c")]
+#[inline]
+pub unsafe fn f16_to_f32_x86_f16c(bits: u16) -> f32 {
+    let bits = _mm_cvtsi32_si128(bits as i32);
+    let result = _mm_cvtph_ps(bits);
+    _mm_cvtss_f32(result)
+}
+
+#[cfg_attr(not(any(feature = "std", target_feature = "f16c")), allow(dead_code))]
+#[target_feature(enable = "f16c")]
+#[inline]
+pub unsafe fn f16_to_f64_x86_f16c(bits: u16) -> f64 {
+    f16_to_f32_x86_f16c(bits) as f64
+}
+
+// Block conversions for the slice APIs: each processes a fixed-width chunk with one intrinsic,
+// reading/writing through raw pointers so callers can slide a window across a slice without
+// requiring it to be aligned or a multiple of the block width.
+
+// Only called from f16::from_f32_slice's vectorized path, which is gated on `feature = "std"`
+// (it allocates a Vec) -- gate these the same way so they don't go dead under no_std.
+#[cfg(feature = "std")]
+#[target_feature(enable = "f16c")]
+#[inline]
+pub unsafe fn f16_from_f32_block4_x86_f16c(src: *const f32, dst: *mut u16) {
+    let values = _mm_loadu_ps(src);
+    let result = _mm_cvtps_ph(values, _MM_FROUND_TO_NEAREST_INT);
+    _mm_storel_epi64(dst as *mut __m128i, result);
+}
+
+#[cfg(feature = "std")]
+#[target_feature(enable = "avx", enable = "f16c")]
+#[inline]
+pub unsafe fn f16_from_f32_block8_x86_f16c(src: *const f32, dst: *mut u16) {
+    let values = _mm256_loadu_ps(src);
+    let result = _mm256_cvtps_ph(values, _MM_FROUND_TO_NEAREST_INT);
+    _mm_storeu_si128(dst as *mut __m128i, result);
+}
+
+#[target_feature(enable = "f16c")]
+#[inline]
+pub unsafe fn f16_to_f32_block4_x86_f16c(src: *const u16, dst: *mut f32) {
+    let bits = _mm_loadl_epi64(src as *const __m128i);
+    let result = _mm_cvtph_ps(bits);
+    _mm_storeu_ps(dst, result);
+}
+
+#[target_feature(enable = "avx", enable = "f16c")]
+#[inline]
+pub unsafe fn f16_to_f32_block8_x86_f16c(src: *const u16, dst: *mut f32) {
+    let bits = _mm_loadu_si128(src as *const __m128i);
+    let result = _mm256_cvtph_ps(bits);
+    _mm256_storeu_ps(dst, result);
+}